@@ -21,6 +21,15 @@ pub enum ReaderError {
     #[diagnostic(code(libnres::convert_error))]
     ConvertValue(#[from] ConverterError),
 
+    #[error("element \"{name}\" is out of data bounds (position {position:?}, size {size:?}, file list position {list_position:?})")]
+    #[diagnostic(code(libnres::element_bounds_error))]
+    IncorrectElement {
+        name: String,
+        position: u32,
+        size: u32,
+        list_position: u64,
+    },
+
     #[error("incorrect header format")]
     #[diagnostic(code(libnres::list_type_error))]
     IncorrectHeader,
@@ -29,12 +38,35 @@ pub enum ReaderError {
     #[diagnostic(code(libnres::file_size_error))]
     IncorrectSizeFile { expected: u32, received: u32 },
 
+    #[error("file list of {total:?} elements does not fit into the file ({size:?} bytes)")]
+    #[diagnostic(code(libnres::list_position_error))]
+    IncorrectPositionList { total: u32, size: u32 },
+
     #[error(
         "incorrect size of the file list (not a multiple of {expected:?}, received {received:?})"
     )]
     #[diagnostic(code(libnres::list_size_error))]
     IncorrectSizeList { expected: u32, received: u32 },
 
+    #[error("element \"{name}\" reading error (position {position:?}, size {size:?})")]
+    #[diagnostic(code(libnres::element_io_error))]
+    ReadElement {
+        name: String,
+        position: u32,
+        size: u32,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("file list reading error (position {position:?}, size {size:?})")]
+    #[diagnostic(code(libnres::list_io_error))]
+    ReadList {
+        position: u64,
+        size: usize,
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error("resource file reading error")]
     #[diagnostic(code(libnres::io_error))]
     ReadFile(#[from] std::io::Error),
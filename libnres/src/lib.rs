@@ -2,6 +2,14 @@
 pub const FILE_TYPE_1: u32 = 1936020046;
 /// Second constant value of the NRes file
 pub const FILE_TYPE_2: u32 = 256;
+/// Known element kinds (stored in the element extension) with their descriptions.
+/// Numeric kinds such as the terrain chunks 3, 5 and 21 are not listed.
+pub const KNOWN_KINDS: &[(&str, &str)] = &[
+    ("MAT0", "Material"),
+    ("MESH", "Model"),
+    ("TEXM", "Texture"),
+    ("WEAR", "Material table"),
+];
 /// Size of the element item (in bytes)
 pub const LIST_ELEMENT_SIZE: u32 = 64;
 /// Minimum allowed file size (in bytes)
@@ -3,7 +3,9 @@ use std::io::{Read, Seek};
 use byteorder::ByteOrder;
 
 use crate::error::ReaderError;
-use crate::{converter, FILE_TYPE_1, FILE_TYPE_2, LIST_ELEMENT_SIZE, MINIMUM_FILE_SIZE};
+use crate::{
+    converter, FILE_TYPE_1, FILE_TYPE_2, KNOWN_KINDS, LIST_ELEMENT_SIZE, MINIMUM_FILE_SIZE,
+};
 
 #[derive(Debug)]
 pub struct ListElement {
@@ -30,47 +32,178 @@ impl ListElement {
     pub fn get_filename(&self) -> String {
         format!("{}.{}", self.name, self.extension)
     }
+
+    /// Check if the file name starts with the prefix (case-insensitive)
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.name.to_lowercase().starts_with(&prefix.to_lowercase())
+    }
+
+    /// Check if the full file name matches the glob pattern with `*` and `?` (case-insensitive)
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        let filename: Vec<char> = self.get_filename().to_lowercase().chars().collect();
+        let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+        match_glob(&filename, &pattern)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayloadType {
+    /// Nested NRes file
+    Nres,
+    /// Plain CP1251 text
+    Text,
+    /// Unrecognized data
+    Unknown,
+    /// RIFF WAVE sound
+    Wav,
+}
+
+#[derive(Debug)]
+pub struct RawListElement {
+    /// Identifier or sequence number
+    pub index: u32,
+    /// Element bytes as stored in the file list: extension (0..4), unknown
+    /// parameters (4..12), file size (12..16), unknown parameter (16..20),
+    /// file name (20..56), position (56..60) and index (60..64)
+    pub data: [u8; 64],
 }
 
 #[derive(Debug)]
 pub struct FileHeader {
     /// File size
-    size: u32,
+    pub size: u32,
     /// Number of files
-    total: u32,
+    pub total: u32,
     /// First constant value
-    type1: u32,
+    pub type1: u32,
     /// Second constant value
-    type2: u32,
+    pub type2: u32,
+}
+
+impl FileHeader {
+    /// Get position of the file list
+    pub fn get_list_position(&self) -> Result<u64, ReaderError> {
+        let (position, _) = get_list_position(self)?;
+        Ok(position)
+    }
+}
+
+/// Detect a type of the packed file data by its content
+pub fn detect_payload_type(buffer: &[u8]) -> PayloadType {
+    if buffer.len() >= 8
+        && byteorder::LittleEndian::read_u32(&buffer[0..4]) == FILE_TYPE_1
+        && byteorder::LittleEndian::read_u32(&buffer[4..8]) == FILE_TYPE_2
+    {
+        return PayloadType::Nres;
+    }
+
+    if buffer.len() >= 12 && &buffer[0..4] == b"RIFF" && &buffer[8..12] == b"WAVE" {
+        return PayloadType::Wav;
+    }
+
+    // Control characters other than tabs and line breaks, and the undefined CP1251 byte
+    let is_text = |byte: &u8| {
+        matches!(byte, b'\t' | b'\n' | b'\r') || (*byte >= 0x20 && *byte != 0x7F && *byte != 0x98)
+    };
+
+    if !buffer.is_empty() && buffer.iter().all(is_text) {
+        return PayloadType::Text;
+    }
+
+    PayloadType::Unknown
 }
 
 /// Get a packed file data
 pub fn get_file(file: &std::fs::File, element: &ListElement) -> Result<Vec<u8>, ReaderError> {
+    let header = get_header(file)?;
+    check_element(element, &header)?;
+
+    let data = get_element_data(file, element)?;
+    Ok(data)
+}
+
+/// Get a header of the file
+pub fn get_header(file: &std::fs::File) -> Result<FileHeader, ReaderError> {
     let size = get_file_size(file)?;
     check_file_size(size)?;
 
     let header = get_file_header(file)?;
     check_file_header(&header, size)?;
 
-    let data = get_element_data(file, element)?;
-    Ok(data)
+    Ok(header)
 }
 
 /// Get a list of packed files
 pub fn get_list(file: &std::fs::File) -> Result<Vec<ListElement>, ReaderError> {
     let mut list: Vec<ListElement> = Vec::new();
 
-    let size = get_file_size(file)?;
-    check_file_size(size)?;
+    let header = get_header(file)?;
+    get_file_list(file, &header, &mut list)?;
 
-    let header = get_file_header(file)?;
-    check_file_header(&header, size)?;
+    Ok(list)
+}
 
-    get_file_list(file, &header, &mut list)?;
+/// Get a list of packed files of the given kind (element extension)
+pub fn get_list_by_kind(file: &std::fs::File, kind: &str) -> Result<Vec<ListElement>, ReaderError> {
+    let mut list = get_list(file)?;
+    list.retain(|element| element.extension.eq_ignore_ascii_case(kind));
+    Ok(list)
+}
+
+/// Get a list of packed files with names starting with the prefix
+pub fn get_list_by_prefix(
+    file: &std::fs::File,
+    prefix: &str,
+) -> Result<Vec<ListElement>, ReaderError> {
+    let mut list = get_list(file)?;
+    list.retain(|element| element.has_prefix(prefix));
+    Ok(list)
+}
 
+/// Get a list of packed files with full names matching the glob pattern
+pub fn get_list_by_glob(
+    file: &std::fs::File,
+    pattern: &str,
+) -> Result<Vec<ListElement>, ReaderError> {
+    let mut list = get_list(file)?;
+    list.retain(|element| element.matches_glob(pattern));
     Ok(list)
 }
 
+/// Get a description of the element kind
+pub fn get_kind_name(kind: &str) -> Option<&'static str> {
+    KNOWN_KINDS
+        .iter()
+        .find(|(value, _)| value.eq_ignore_ascii_case(kind))
+        .map(|(_, name)| *name)
+}
+
+/// Get a list of packed files without decoding the elements
+pub fn get_raw_list(file: &std::fs::File) -> Result<Vec<RawListElement>, ReaderError> {
+    let mut list: Vec<RawListElement> = Vec::new();
+
+    let header = get_header(file)?;
+    get_raw_file_list(file, &header, &mut list)?;
+
+    Ok(list)
+}
+
+fn check_element(element: &ListElement, header: &FileHeader) -> Result<(), ReaderError> {
+    let (list_position, _) = get_list_position(header)?;
+    let end = converter::u32_to_u64(element.position)? + converter::u32_to_u64(element.size)?;
+
+    if (element.size > 0 && element.position < MINIMUM_FILE_SIZE) || end > list_position {
+        return Err(ReaderError::IncorrectElement {
+            name: element.get_filename(),
+            position: element.position,
+            size: element.size,
+            list_position,
+        });
+    }
+
+    Ok(())
+}
+
 fn check_file_header(header: &FileHeader, size: u32) -> Result<(), ReaderError> {
     if header.type1 != FILE_TYPE_1 || header.type2 != FILE_TYPE_2 {
         return Err(ReaderError::IncorrectHeader);
@@ -97,6 +230,11 @@ fn check_file_size(size: u32) -> Result<(), ReaderError> {
     Ok(())
 }
 
+fn decode_name(buffer: &[u8]) -> String {
+    let (value, _) = encoding_rs::WINDOWS_1251.decode_without_bom_handling(buffer);
+    value.trim_matches(char::from(0)).to_string()
+}
+
 fn get_element_data(file: &std::fs::File, element: &ListElement) -> Result<Vec<u8>, ReaderError> {
     let position = converter::u32_to_u64(element.position)?;
     let size = converter::u32_to_usize(element.size)?;
@@ -104,12 +242,19 @@ fn get_element_data(file: &std::fs::File, element: &ListElement) -> Result<Vec<u
     let mut reader = std::io::BufReader::new(file);
     let mut buffer = vec![0u8; size];
 
+    let read_error = |error| ReaderError::ReadElement {
+        name: element.get_filename(),
+        position: element.position,
+        size: element.size,
+        source: error,
+    };
+
     if let Err(error) = reader.seek(std::io::SeekFrom::Start(position)) {
-        return Err(ReaderError::ReadFile(error));
+        return Err(read_error(error));
     };
 
     if let Err(error) = reader.read_exact(&mut buffer) {
-        return Err(ReaderError::ReadFile(error));
+        return Err(read_error(error));
     };
 
     Ok(buffer)
@@ -149,16 +294,57 @@ fn get_file_list(
     header: &FileHeader,
     list: &mut Vec<ListElement>,
 ) -> Result<(), ReaderError> {
+    let mut raw_list: Vec<RawListElement> = Vec::new();
+    get_raw_file_list(file, header, &mut raw_list)?;
+
+    for raw_element in raw_list {
+        let element = get_list_element(&raw_element.data)?;
+        list.push(element);
+    }
+
+    Ok(())
+}
+
+fn get_raw_file_list(
+    file: &std::fs::File,
+    header: &FileHeader,
+    list: &mut Vec<RawListElement>,
+) -> Result<(), ReaderError> {
+    let mut buffer = get_list_buffer(file, header)?;
+    let buffer_size = converter::usize_to_u32(buffer.len())?;
+
+    for i in 0..(buffer_size / LIST_ELEMENT_SIZE) {
+        let (from, to) = get_element_position(i)?;
+        let mut data = [0u8; 64];
+        data.copy_from_slice(&buffer[from..to]);
+
+        list.push(RawListElement {
+            index: byteorder::LittleEndian::read_u32(&data[60..64]),
+            data,
+        });
+    }
+
+    buffer.clear();
+    Ok(())
+}
+
+fn get_list_buffer(file: &std::fs::File, header: &FileHeader) -> Result<Vec<u8>, ReaderError> {
     let (start_position, list_size) = get_list_position(header)?;
     let mut reader = std::io::BufReader::new(file);
     let mut buffer = vec![0u8; list_size];
 
+    let read_error = |error| ReaderError::ReadList {
+        position: start_position,
+        size: list_size,
+        source: error,
+    };
+
     if let Err(error) = reader.seek(std::io::SeekFrom::Start(start_position)) {
-        return Err(ReaderError::ReadFile(error));
+        return Err(read_error(error));
     };
 
     if let Err(error) = reader.read_exact(&mut buffer) {
-        return Err(ReaderError::ReadFile(error));
+        return Err(read_error(error));
     }
 
     let buffer_size = converter::usize_to_u32(buffer.len())?;
@@ -170,16 +356,35 @@ fn get_file_list(
         });
     }
 
-    for i in 0..(buffer_size / LIST_ELEMENT_SIZE) {
-        let (from, to) = get_element_position(i)?;
-        let chunk: &[u8] = &buffer[from..to];
+    Ok(buffer)
+}
 
-        let element = get_list_element(chunk)?;
-        list.push(element);
+fn match_glob(value: &[char], pattern: &[char]) -> bool {
+    let (mut value_index, mut pattern_index) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while value_index < value.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                backtrack = Some((pattern_index, value_index));
+                pattern_index += 1;
+            }
+            Some(symbol) if *symbol == '?' || *symbol == value[value_index] => {
+                value_index += 1;
+                pattern_index += 1;
+            }
+            _ => match backtrack {
+                Some((star_index, star_value_index)) => {
+                    backtrack = Some((star_index, star_value_index + 1));
+                    pattern_index = star_index + 1;
+                    value_index = star_value_index + 1;
+                }
+                None => return false,
+            },
+        }
     }
 
-    buffer.clear();
-    Ok(())
+    pattern[pattern_index..].iter().all(|symbol| *symbol == '*')
 }
 
 fn get_file_size(file: &std::fs::File) -> Result<u32, ReaderError> {
@@ -200,13 +405,8 @@ fn get_list_element(buffer: &[u8]) -> Result<ListElement, ReaderError> {
     let unknown1 = byteorder::LittleEndian::read_i32(&buffer[8..12]);
     let unknown2 = byteorder::LittleEndian::read_i32(&buffer[16..20]);
 
-    let extension = String::from_utf8_lossy(&buffer[0..4])
-        .trim_matches(char::from(0))
-        .to_string();
-
-    let name = String::from_utf8_lossy(&buffer[20..56])
-        .trim_matches(char::from(0))
-        .to_string();
+    let extension = decode_name(&buffer[0..4]);
+    let name = decode_name(&buffer[20..56]);
 
     Ok(ListElement {
         _unknown0: unknown0,
@@ -221,7 +421,245 @@ fn get_list_element(buffer: &[u8]) -> Result<ListElement, ReaderError> {
 }
 
 fn get_list_position(header: &FileHeader) -> Result<(u64, usize), ReaderError> {
-    let position = converter::u32_to_u64(header.size - (header.total * LIST_ELEMENT_SIZE))?;
-    let size = converter::u32_to_usize(header.total * LIST_ELEMENT_SIZE)?;
+    let list_size = header
+        .total
+        .checked_mul(LIST_ELEMENT_SIZE)
+        .filter(|list_size| *list_size <= header.size.saturating_sub(MINIMUM_FILE_SIZE));
+
+    let list_size = match list_size {
+        None => {
+            return Err(ReaderError::IncorrectPositionList {
+                total: header.total,
+                size: header.size,
+            })
+        }
+        Some(value) => value,
+    };
+
+    let position = converter::u32_to_u64(header.size - list_size)?;
+    let size = converter::u32_to_usize(list_size)?;
     Ok((position, size))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use byteorder::ByteOrder;
+
+    use super::*;
+
+    fn create_file(
+        dir: &tempdir::TempDir,
+        total: u32,
+        content: &[u8],
+        list: &[[u8; 64]],
+    ) -> std::fs::File {
+        let mut buffer = vec![0u8; MINIMUM_FILE_SIZE as usize];
+        buffer.extend_from_slice(content);
+        list.iter().for_each(|row| buffer.extend_from_slice(row));
+
+        let size = buffer.len() as u32;
+        byteorder::LittleEndian::write_u32(&mut buffer[0..4], FILE_TYPE_1);
+        byteorder::LittleEndian::write_u32(&mut buffer[4..8], FILE_TYPE_2);
+        byteorder::LittleEndian::write_u32(&mut buffer[8..12], total);
+        byteorder::LittleEndian::write_u32(&mut buffer[12..16], size);
+
+        let path = dir.path().join("file.lib");
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(&buffer).unwrap();
+
+        std::fs::File::open(&path).unwrap()
+    }
+
+    fn create_row(name: &str, position: u32, size: u32) -> [u8; 64] {
+        create_row_with_extension("TXT", name, position, size)
+    }
+
+    fn create_row_with_extension(
+        extension: &str,
+        name: &str,
+        position: u32,
+        size: u32,
+    ) -> [u8; 64] {
+        let mut row = [0u8; 64];
+        row[0..extension.len()].copy_from_slice(extension.as_bytes());
+        row[20..20 + name.len()].copy_from_slice(name.as_bytes());
+        byteorder::LittleEndian::write_u32(&mut row[12..16], size);
+        byteorder::LittleEndian::write_u32(&mut row[56..60], position);
+        row
+    }
+
+    #[test]
+    fn get_list_rejects_overflowing_total() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let file = create_file(&dir, 0x04000001, &[], &[]);
+
+        let result = get_list(&file);
+        assert!(matches!(
+            result,
+            Err(ReaderError::IncorrectPositionList {
+                total: 0x04000001,
+                size: 16
+            })
+        ));
+    }
+
+    #[test]
+    fn get_file_rejects_oversized_element() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let row = create_row("HUGE", 16, 0xFFFFFFF0);
+        let file = create_file(&dir, 1, &[0u8; 8], &[row]);
+        let list = get_list(&file).unwrap();
+
+        let result = get_file(&file, &list[0]);
+        assert!(matches!(
+            result,
+            Err(ReaderError::IncorrectElement {
+                position: 16,
+                size: 0xFFFFFFF0,
+                list_position: 24,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn get_file_rejects_element_overlapping_list() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let row = create_row("OVERLAP", 16, 9);
+        let file = create_file(&dir, 1, &[0u8; 8], &[row]);
+        let list = get_list(&file).unwrap();
+
+        let result = get_file(&file, &list[0]);
+        assert!(matches!(
+            result,
+            Err(ReaderError::IncorrectElement { size: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn get_file_reads_empty_element() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let rows = [create_row("EMPTY", 0, 0), create_row("DATA", 16, 8)];
+        let file = create_file(&dir, 2, b"abcdefgh", &rows);
+        let list = get_list(&file).unwrap();
+
+        assert!(get_file(&file, &list[0]).unwrap().is_empty());
+        assert_eq!(get_file(&file, &list[1]).unwrap(), b"abcdefgh");
+    }
+
+    #[test]
+    fn get_list_by_kind_filters_elements() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let rows = [
+            create_row_with_extension("TEXM", "SKY", 16, 4),
+            create_row_with_extension("MAT0", "SKY", 20, 4),
+        ];
+        let file = create_file(&dir, 2, &[0u8; 8], &rows);
+
+        let list = get_list_by_kind(&file, "texm").unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].extension, "TEXM");
+        assert_eq!(get_kind_name(&list[0].extension), Some("Texture"));
+        assert_eq!(get_kind_name("TXT"), None);
+    }
+
+    #[test]
+    fn get_header_and_raw_list_read_file_list() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let mut row = create_row("DATA", 16, 8);
+        byteorder::LittleEndian::write_u32(&mut row[60..64], 7);
+        let file = create_file(&dir, 1, b"abcdefgh", &[row]);
+
+        let header = get_header(&file).unwrap();
+        assert_eq!(header.type1, FILE_TYPE_1);
+        assert_eq!(header.type2, FILE_TYPE_2);
+        assert_eq!(header.total, 1);
+        assert_eq!(header.size, 88);
+        assert_eq!(header.get_list_position().unwrap(), 24);
+
+        let list = get_raw_list(&file).unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].index, 7);
+        assert_eq!(list[0].data, row);
+    }
+
+    #[test]
+    fn get_list_decodes_cp1251_names() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let mut row = create_row("", 16, 8);
+        row[20..24].copy_from_slice(&[0xD2, 0xC5, 0xD1, 0xD2]);
+        let file = create_file(&dir, 1, b"abcdefgh", &[row]);
+
+        let list = get_list(&file).unwrap();
+        assert_eq!(list[0].name, "ТЕСТ");
+
+        let raw_list = get_raw_list(&file).unwrap();
+        assert_eq!(raw_list[0].data[20..24], [0xD2, 0xC5, 0xD1, 0xD2]);
+        assert_eq!(raw_list[0].data[24..56], [0u8; 32]);
+    }
+
+    #[test]
+    fn get_element_data_reports_element_on_read_error() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let row = create_row("DATA", 16, 8);
+        let file = create_file(&dir, 1, b"abcdefgh", &[row]);
+        let list = get_list(&file).unwrap();
+
+        let short_dir = tempdir::TempDir::new("libnres").unwrap();
+        let short_file = create_file(&short_dir, 0, &[], &[]);
+
+        let result = get_element_data(&short_file, &list[0]);
+        assert!(matches!(
+            result,
+            Err(ReaderError::ReadElement {
+                position: 16,
+                size: 8,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn detect_payload_type_recognizes_content() {
+        let mut nres = [0u8; 16];
+        byteorder::LittleEndian::write_u32(&mut nres[0..4], FILE_TYPE_1);
+        byteorder::LittleEndian::write_u32(&mut nres[4..8], FILE_TYPE_2);
+
+        assert_eq!(detect_payload_type(&nres), PayloadType::Nres);
+        assert_eq!(
+            detect_payload_type(b"RIFF\x24\0\0\0WAVEfmt "),
+            PayloadType::Wav
+        );
+        assert_eq!(
+            detect_payload_type(b"\xD2\xC5\xD1\xD2 1\r\n"),
+            PayloadType::Text
+        );
+        assert_eq!(detect_payload_type(&[0u8, 1, 2, 3]), PayloadType::Unknown);
+        assert_eq!(detect_payload_type(&[]), PayloadType::Unknown);
+    }
+
+    #[test]
+    fn get_list_by_prefix_and_glob_filter_elements() {
+        let dir = tempdir::TempDir::new("libnres").unwrap();
+        let rows = [
+            create_row_with_extension("TEXM", "UI_MAP", 16, 4),
+            create_row_with_extension("MSH", "LAND", 20, 4),
+        ];
+        let file = create_file(&dir, 2, &[0u8; 8], &rows);
+
+        let list = get_list_by_prefix(&file, "ui_").unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].name, "UI_MAP");
+
+        let list = get_list_by_glob(&file, "*.msh").unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].name, "LAND");
+
+        assert!(list[0].matches_glob("L?ND.*"));
+        assert!(list[0].matches_glob("*A*D*"));
+        assert!(!list[0].matches_glob("LAND"));
+        assert!(!list[0].matches_glob("*.TEXM"));
+    }
+}
@@ -1,10 +1,13 @@
 extern crate core;
 extern crate libnres;
 
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
 use clap::{Parser, Subcommand};
 use miette::{IntoDiagnostic, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 
 #[derive(Parser, Debug)]
 #[command(name = "NRes CLI")]
@@ -14,6 +17,22 @@ struct Cli {
     command: Commands,
 }
 
+#[derive(Serialize, Debug)]
+struct DuplicateElement {
+    archive: String,
+    extension: String,
+    index: u32,
+    name: String,
+}
+
+#[derive(Serialize, Debug)]
+struct DuplicateGroup {
+    hash: String,
+    size: u32,
+    original: DuplicateElement,
+    duplicates: Vec<DuplicateElement>,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Check if the "NRes" file can be extract
@@ -30,6 +49,16 @@ enum Commands {
         #[arg(long)]
         name: Option<String>,
     },
+    /// Print files with identical content across the "NRes" files
+    #[command(arg_required_else_help = true)]
+    Duplicates {
+        /// "NRes" files
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Write the duplicates mapping to a JSON file
+        #[arg(long, value_name = "FILE")]
+        json: Option<String>,
+    },
     /// Extract files or a file from the "NRes" file
     #[command(arg_required_else_help = true)]
     Extract {
@@ -47,6 +76,15 @@ enum Commands {
     Ls {
         /// "NRes" file
         file: String,
+        /// Filter results by element kind
+        #[arg(long)]
+        kind: Option<String>,
+        /// Filter results by file name prefix
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Filter results by full file name glob pattern (`*` and `?`)
+        #[arg(long)]
+        glob: Option<String>,
     },
 }
 
@@ -57,8 +95,14 @@ pub fn main() -> Result<()> {
     match cli.command {
         Commands::Check { file } => command_check(stdout, file)?,
         Commands::Debug { file, name } => command_debug(stdout, file, name)?,
+        Commands::Duplicates { files, json } => command_duplicates(stdout, files, json)?,
         Commands::Extract { file, force, out } => command_extract(stdout, file, out, force)?,
-        Commands::Ls { file } => command_ls(stdout, file)?,
+        Commands::Ls {
+            file,
+            kind,
+            prefix,
+            glob,
+        } => command_ls(stdout, file, kind, prefix, glob)?,
     }
 
     Ok(())
@@ -91,20 +135,27 @@ fn command_check(_stdout: console::Term, file: String) -> Result<()> {
 
 fn command_debug(stdout: console::Term, file: String, name: Option<String>) -> Result<()> {
     let file = std::fs::File::open(file).into_diagnostic()?;
+    let header = libnres::reader::get_header(&file).into_diagnostic()?;
     let mut list = libnres::reader::get_list(&file).into_diagnostic()?;
 
-    let mut total_files_size: u32 = 0;
-    let mut total_files_gap: u32 = 0;
+    let list_position = header.get_list_position().into_diagnostic()?;
+    let text = format!(
+        "Header: {:#?};\nList position: {};\n",
+        header, list_position
+    );
+    stdout.write_line(&text).into_diagnostic()?;
+
+    let mut total_files_size: u64 = 0;
+    let mut total_files_gap: u64 = 0;
     let mut total_files: u32 = 0;
 
     for (index, item) in list.iter().enumerate() {
-        total_files_size += item.size;
+        total_files_size += u64::from(item.size);
         total_files += 1;
         let mut gap = 0;
 
-        if index > 1 {
-            let previous_item = &list[index - 1];
-            gap = item.position - (previous_item.position + previous_item.size);
+        if index > 0 {
+            gap = get_gap(&list[index - 1], item);
         }
 
         total_files_gap += gap;
@@ -117,12 +168,19 @@ fn command_debug(stdout: console::Term, file: String, name: Option<String>) -> R
     for (index, item) in list.iter().enumerate() {
         let mut gap = 0;
 
-        if index > 1 {
-            let previous_item = &list[index - 1];
-            gap = item.position - (previous_item.position + previous_item.size);
+        if index > 0 {
+            gap = get_gap(&list[index - 1], item);
         }
 
-        let text = format!("Index: {};\nGap: {};\nItem: {:#?};\n", index, gap, item);
+        let kind = libnres::reader::get_kind_name(&item.extension).unwrap_or("Unknown");
+        let detected = match libnres::reader::get_file(&file, item) {
+            Ok(buffer) => format!("{:?}", libnres::reader::detect_payload_type(&buffer)),
+            Err(error) => format!("Unreadable ({})", error),
+        };
+        let text = format!(
+            "Index: {};\nGap: {};\nKind: {};\nDetected: {};\nItem: {:#?};\n",
+            index, gap, kind, detected, item
+        );
         stdout.write_line(&text).into_diagnostic()?;
     }
 
@@ -136,6 +194,141 @@ fn command_debug(stdout: console::Term, file: String, name: Option<String>) -> R
     Ok(())
 }
 
+fn command_duplicates(
+    stdout: console::Term,
+    files: Vec<String>,
+    json: Option<String>,
+) -> Result<()> {
+    let mut archives: Vec<(String, std::fs::File)> = Vec::new();
+    let mut paths: HashSet<std::path::PathBuf> = HashSet::new();
+    let mut sizes: HashMap<u32, Vec<(usize, libnres::reader::ListElement)>> = HashMap::new();
+
+    for path in files {
+        if !paths.insert(std::fs::canonicalize(&path).into_diagnostic()?) {
+            continue;
+        }
+
+        let file = std::fs::File::open(&path).into_diagnostic()?;
+        let list = libnres::reader::get_list(&file).into_diagnostic()?;
+
+        for element in list {
+            if element.size == 0 {
+                continue;
+            }
+
+            sizes
+                .entry(element.size)
+                .or_default()
+                .push((archives.len(), element));
+        }
+
+        archives.push((path, file));
+    }
+
+    let mut sizes: Vec<(u32, Vec<(usize, libnres::reader::ListElement)>)> = sizes
+        .into_iter()
+        .filter(|(_, elements)| elements.len() > 1)
+        .collect();
+    sizes.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+
+    for (size, elements) in sizes {
+        // Elements with the same digest, the first one of each group is compared byte for byte
+        let mut digests: HashMap<[u8; 32], Vec<Vec<(usize, libnres::reader::ListElement)>>> =
+            HashMap::new();
+
+        for (archive, element) in elements {
+            let buffer =
+                libnres::reader::get_file(&archives[archive].1, &element).into_diagnostic()?;
+            let digest: [u8; 32] = Sha256::digest(&buffer).into();
+            let candidates = digests.entry(digest).or_default();
+
+            let mut found = None;
+
+            for (position, candidate) in candidates.iter().enumerate() {
+                let (original_archive, original) = &candidate[0];
+                let original_buffer =
+                    libnres::reader::get_file(&archives[*original_archive].1, original)
+                        .into_diagnostic()?;
+
+                if original_buffer == buffer {
+                    found = Some(position);
+                    break;
+                }
+            }
+
+            match found {
+                Some(position) => candidates[position].push((archive, element)),
+                None => candidates.push(vec![(archive, element)]),
+            }
+        }
+
+        let mut size_groups: Vec<DuplicateGroup> = Vec::new();
+
+        for (digest, candidates) in digests {
+            for candidate in candidates {
+                if candidate.len() < 2 {
+                    continue;
+                }
+
+                let mut items: Vec<DuplicateElement> = candidate
+                    .into_iter()
+                    .map(|(archive, element)| DuplicateElement {
+                        archive: archives[archive].0.to_string(),
+                        extension: element.extension,
+                        index: element.index,
+                        name: element.name,
+                    })
+                    .collect();
+
+                let original = items.remove(0);
+                size_groups.push(DuplicateGroup {
+                    hash: digest.iter().map(|byte| format!("{:02x}", byte)).collect(),
+                    size,
+                    original,
+                    duplicates: items,
+                });
+            }
+        }
+
+        size_groups.sort_by(|a, b| a.hash.cmp(&b.hash));
+        groups.extend(size_groups);
+    }
+
+    let mut total_files: u64 = 0;
+    let mut total_files_size: u64 = 0;
+
+    for group in &groups {
+        total_files += group.duplicates.len() as u64;
+        total_files_size += u64::from(group.size) * group.duplicates.len() as u64;
+
+        let text = format!(
+            "Hash: {};\nSize: {} (bytes);\nOriginal: {:#?};\nDuplicates: {:#?};\n",
+            group.hash, group.size, group.original, group.duplicates
+        );
+        stdout.write_line(&text).into_diagnostic()?;
+    }
+
+    let text = format!(
+        "Total duplicate groups: {};\nTotal duplicates: {};\nTotal duplicates size: {} (bytes);",
+        groups.len(),
+        total_files,
+        total_files_size
+    );
+
+    stdout.write_line(&text).into_diagnostic()?;
+
+    if let Some(json) = json {
+        let file = std::fs::File::create(json).into_diagnostic()?;
+        let mut writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &groups).into_diagnostic()?;
+        writer.flush().into_diagnostic()?;
+    }
+
+    Ok(())
+}
+
 fn command_extract(_stdout: console::Term, file: String, out: String, force: bool) -> Result<()> {
     let file = std::fs::File::open(file).into_diagnostic()?;
     let list = libnres::reader::get_list(&file).into_diagnostic()?;
@@ -173,9 +366,26 @@ fn command_extract(_stdout: console::Term, file: String, out: String, force: boo
     Ok(())
 }
 
-fn command_ls(stdout: console::Term, file: String) -> Result<()> {
+fn command_ls(
+    stdout: console::Term,
+    file: String,
+    kind: Option<String>,
+    prefix: Option<String>,
+    glob: Option<String>,
+) -> Result<()> {
     let file = std::fs::File::open(file).into_diagnostic()?;
-    let list = libnres::reader::get_list(&file).into_diagnostic()?;
+    let mut list = match kind {
+        Some(kind) => libnres::reader::get_list_by_kind(&file, &kind).into_diagnostic()?,
+        None => libnres::reader::get_list(&file).into_diagnostic()?,
+    };
+
+    if let Some(prefix) = prefix {
+        list.retain(|item| item.has_prefix(&prefix));
+    };
+
+    if let Some(glob) = glob {
+        list.retain(|item| item.matches_glob(&glob));
+    };
 
     for element in list {
         stdout.write_line(&element.name).into_diagnostic()?;
@@ -192,6 +402,14 @@ fn get_bar_style() -> Result<indicatif::ProgressStyle> {
     )
 }
 
+fn get_gap(
+    previous_item: &libnres::reader::ListElement,
+    item: &libnres::reader::ListElement,
+) -> u64 {
+    let previous_end = u64::from(previous_item.position) + u64::from(previous_item.size);
+    u64::from(item.position).saturating_sub(previous_end)
+}
+
 fn is_exist_file(path: &String) -> bool {
     let metadata = std::path::Path::new(path);
     metadata.exists()
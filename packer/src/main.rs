@@ -5,6 +5,7 @@ use std::{
 };
 
 use byteorder::{ByteOrder, LittleEndian};
+use encoding_rs::WINDOWS_1251;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -38,6 +39,25 @@ fn main() {
     pack(String::from(input), String::from(output));
 }
 
+fn encode_name(value: &str, max_size: usize) -> Vec<u8> {
+    let (buffer, _, had_errors) = WINDOWS_1251.encode(value);
+
+    if had_errors {
+        panic!("name \"{}\" can't be encoded in CP1251", value);
+    }
+
+    if buffer.len() > max_size {
+        panic!(
+            "name \"{}\" can't be encoded in {} bytes (received {} bytes)",
+            value,
+            max_size,
+            buffer.len()
+        );
+    }
+
+    buffer.into_owned()
+}
+
 fn pack(input: String, output: String) {
     // Загружаем индекс-файл
     let index_file = format!("{}/{}", input, "index.json");
@@ -92,7 +112,7 @@ fn pack(input: String, output: String) {
 
         // Пишем тип файла
         let mut extension_buffer: [u8; 4] = [0; 4];
-        let mut file_extension_buffer = element.extension.into_bytes();
+        let mut file_extension_buffer = encode_name(&element.extension, 4);
         file_extension_buffer.resize(4, 0);
         extension_buffer.copy_from_slice(&file_extension_buffer);
         element_buffer.extend(extension_buffer);
@@ -119,7 +139,7 @@ fn pack(input: String, output: String) {
 
         // Пишем название файла
         let mut name_buffer: [u8; 36] = [0; 36];
-        let mut file_name_buffer = element.name.into_bytes();
+        let mut file_name_buffer = encode_name(&element.name, 36);
         file_name_buffer.resize(36, 0);
         name_buffer.copy_from_slice(&file_name_buffer);
         element_buffer.extend(name_buffer);
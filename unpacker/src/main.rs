@@ -3,6 +3,7 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 
 use byteorder::{ByteOrder, LittleEndian};
+use encoding_rs::WINDOWS_1251;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -36,6 +37,11 @@ fn main() {
     unpack(String::from(input), String::from(output));
 }
 
+fn decode_name(buffer: &[u8]) -> String {
+    let (value, _) = WINDOWS_1251.decode_without_bom_handling(buffer);
+    value.trim_matches(char::from(0)).to_string()
+}
+
 fn unpack(input: String, output: String) {
     let file = File::open(input).unwrap();
     let metadata = file.metadata().unwrap();
@@ -83,13 +89,9 @@ fn unpack(input: String, output: String) {
         let chunk: &[u8] = &list_buffer[from..to];
 
         let element_list = ListElement {
-            extension: String::from_utf8_lossy(&chunk[0..4])
-                .trim_matches(char::from(0))
-                .to_string(),
+            extension: decode_name(&chunk[0..4]),
             index: LittleEndian::read_u32(&chunk[60..64]),
-            name: String::from_utf8_lossy(&chunk[20..56])
-                .trim_matches(char::from(0))
-                .to_string(),
+            name: decode_name(&chunk[20..56]),
             position: LittleEndian::read_u32(&chunk[56..60]),
             size: LittleEndian::read_u32(&chunk[12..16]),
             unknown0: LittleEndian::read_u32(&chunk[4..8]),